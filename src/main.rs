@@ -1,24 +1,41 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use clap::Parser;
 use colored::*;
 use futures::{stream, StreamExt};
+use std::collections::HashSet;
 use std::fs::File;
 use std::io::{BufRead, BufReader, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH}; // Duration is now used
 use tokio::sync::Mutex;
-use trust_dns_resolver::config::{ResolverConfig, ResolverOpts};
+use trust_dns_resolver::config::{
+    NameServerConfig, Protocol, ResolverConfig, ResolverOpts, ServerOrderingStrategy,
+};
+use trust_dns_resolver::proto::rr::{RData, RecordType};
 use trust_dns_resolver::TokioAsyncResolver;
 
+/// Built-in CNAME suffix -> service name fingerprints for takeover detection.
+/// Overridable/extendable via `--fingerprints`.
+const DEFAULT_FINGERPRINTS: &[(&str, &str)] = &[
+    (".github.io", "GitHub Pages"),
+    (".herokudns.com", "Heroku"),
+    (".s3.amazonaws.com", "Amazon S3"),
+    (".azurewebsites.net", "Azure App Service"),
+    (".cloudfront.net", "Amazon CloudFront"),
+    (".wpengine.com", "WP Engine"),
+];
+
 #[derive(Parser, Debug)]
 #[clap(name = "subruster", version = "2.0.0", author = "YourName")]
 struct Args {
-    #[clap(short, long)]
-    domain: String,
+    #[clap(short, long, required_unless_present = "reverse")]
+    domain: Option<String>,
 
-    #[clap(short, long, value_name = "FILE")]
-    wordlist: PathBuf,
+    #[clap(short, long, value_name = "FILE", required_unless_present = "reverse")]
+    wordlist: Option<PathBuf>,
 
     #[clap(short, long, default_value_t = 100)]
     concurrency: usize,
@@ -31,6 +48,63 @@ struct Args {
 
     #[clap(short, long)]
     silent: bool,
+
+    /// Preset resolver ("system", "google", "quad9", "cloudflare") or one-or-more
+    /// explicit IP:port nameserver addresses (comma-separated or repeated).
+    #[clap(long, num_args = 1.., value_delimiter = ',')]
+    resolver: Option<Vec<String>>,
+
+    /// Newline-delimited file of IP:port nameservers, e.g. "1.1.1.1:53".
+    #[clap(long, value_name = "FILE")]
+    resolver_file: Option<PathBuf>,
+
+    /// Extra CNAME fingerprints as "suffix,service" lines. Takes priority over
+    /// the built-ins for overlapping suffixes.
+    #[clap(long, value_name = "FILE")]
+    fingerprints: Option<PathBuf>,
+
+    /// Minimum delay in milliseconds between dispatching successive lookups
+    /// (independent of --concurrency). 0 disables pacing.
+    #[clap(long, default_value_t = 0)]
+    interval: u64,
+
+    /// Route resolution through DNS-over-TLS, falling back to plaintext UDP/53
+    /// if the DoT endpoint is unreachable.
+    #[clap(long)]
+    dot: bool,
+
+    /// DoT server to connect to.
+    #[clap(long, default_value = "1.1.1.1", requires = "dot")]
+    dot_host: String,
+
+    /// DoT port.
+    #[clap(long, default_value_t = 853, requires = "dot")]
+    dot_port: u16,
+
+    /// Number of distinct random-nonce probes used for wildcard detection.
+    #[clap(long, default_value_t = 3)]
+    wildcard_probes: usize,
+
+    /// Skip wildcard detection entirely.
+    #[clap(long)]
+    no_wildcard: bool,
+
+    /// Reverse mode: PTR-lookup IPs from --cidr/--ip-file back to hostnames,
+    /// instead of brute-forcing subdomains of --domain.
+    #[clap(long)]
+    reverse: bool,
+
+    /// CIDR block to expand into host addresses for --reverse, e.g. 203.0.113.0/24.
+    #[clap(long, requires = "reverse")]
+    cidr: Option<String>,
+
+    /// Newline-delimited file of IP addresses for --reverse.
+    #[clap(long, value_name = "FILE", requires = "reverse")]
+    ip_file: Option<PathBuf>,
+
+    /// Feed hostnames discovered by --reverse into the forward-resolution path.
+    #[clap(long, requires = "reverse")]
+    expand: bool,
 }
 
 #[tokio::main]
@@ -39,108 +113,589 @@ async fn main() -> Result<()> {
 
     if !args.silent {
         print_banner();
-        println!(
-            "[*] Target: {}\n[*] Threads: {}\n[*] Wordlist: {:?}",
-            args.domain.cyan(),
-            args.concurrency.to_string().yellow(),
-            args.wordlist
-        );
+        if args.reverse {
+            println!(
+                "[*] Mode: reverse (PTR)\n[*] Threads: {}",
+                args.concurrency.to_string().yellow()
+            );
+        } else {
+            println!(
+                "[*] Target: {}\n[*] Threads: {}\n[*] Wordlist: {:?}",
+                args.domain.as_deref().unwrap_or_default().cyan(),
+                args.concurrency.to_string().yellow(),
+                args.wordlist.as_ref().unwrap()
+            );
+        }
     }
 
-    // FIX 1: Removed .context() check.
-    // The constructor returns the instance directly, not a Result.
-    let resolver = TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default());
+    let resolver = build_resolver(&args).context("Failed to set up resolver")?;
 
-    // Detect wildcard (returns Option<IP_String>)
-    let wildcard_ip = detect_wildcard(&resolver, &args.domain).await;
+    // `use_dot` is flipped once by the startup probe below and only ever read
+    // afterwards, but it's shared across every concurrent lookup task.
+    let use_dot = Arc::new(AtomicBool::new(false));
+    let dot_resolver = if args.dot {
+        let dot = build_dot_resolver(&args).context("Failed to set up DoT resolver")?;
+        let probe = tokio::time::timeout(Duration::from_secs(3), dot.lookup_ip("example.com."))
+            .await;
+        match probe {
+            Ok(Ok(_)) => use_dot.store(true, Ordering::Relaxed),
+            _ => {
+                if !args.silent {
+                    println!(
+                        "{} DNS-over-TLS probe failed, falling back to plaintext UDP/53",
+                        "[!]".yellow()
+                    );
+                }
+            }
+        }
+        Some(dot)
+    } else {
+        None
+    };
 
-    if !args.silent {
-        if let Some(ref ip) = wildcard_ip {
+    let fingerprints = load_fingerprints(args.fingerprints.as_deref())
+        .context("Failed to load fingerprints")?;
+
+    let found_domains = Arc::new(Mutex::new(Vec::new()));
+    let takeovers = Arc::new(Mutex::new(Vec::new()));
+
+    // Pre-calculate timeout duration to avoid doing it inside the loop
+    let timeout_duration = Duration::from_secs(args.timeout);
+
+    // When set, dispatch is paced independently of the concurrency window: every
+    // in-flight lookup still waits its turn on this shared ticker before firing.
+    let dispatch_interval = if args.interval > 0 {
+        let mut ticker = tokio::time::interval(Duration::from_millis(args.interval));
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        Some(Arc::new(Mutex::new(ticker)))
+    } else {
+        None
+    };
+
+    let ctx = ScanContext {
+        resolver: &resolver,
+        dot_resolver: dot_resolver.as_ref(),
+        use_dot: &use_dot,
+        fingerprints: &fingerprints,
+        concurrency: args.concurrency,
+        timeout_duration,
+        dispatch_interval: &dispatch_interval,
+        silent: args.silent,
+    };
+
+    let discovered_hosts = if args.reverse {
+        let ips = load_reverse_targets(&args)?;
+        if !args.silent {
+            println!("[*] Loaded {} addresses. Starting reverse lookup...", ips.len());
+        }
+
+        let discovered = reverse_scan(ips, &ctx).await;
+
+        if args.expand {
+            let names: Vec<String> = discovered
+                .lock()
+                .await
+                .iter()
+                .map(|(_, name)| name.clone())
+                .collect();
+            if !names.is_empty() {
+                if !args.silent {
+                    println!(
+                        "[*] Expanding {} discovered hostnames through forward resolution...",
+                        names.len()
+                    );
+                }
+                scan_names(names, None, &ctx, found_domains.clone(), takeovers.clone()).await;
+            }
+        }
+
+        Some(discovered)
+    } else {
+        let domain = args
+            .domain
+            .clone()
+            .context("--domain is required unless --reverse is set")?;
+        let wordlist = args
+            .wordlist
+            .clone()
+            .context("--wordlist is required unless --reverse is set")?;
+
+        // Detect wildcard DNS: the union of IPs returned across several probes.
+        let wildcard_ips = if args.no_wildcard {
+            None
+        } else {
+            detect_wildcard(&resolver, &domain, args.wildcard_probes).await
+        };
+
+        if !args.silent {
+            if let Some(ref ips) = wildcard_ips {
+                let ips_joined = ips.iter().cloned().collect::<Vec<_>>().join(", ");
+                println!(
+                    "{} Wildcard detected! Filtering results pointing to: {}",
+                    "[!]".yellow(),
+                    ips_joined.red()
+                );
+            }
+        }
+
+        let subdomains = load_wordlist(&wordlist).context("Failed to read wordlist")?;
+        if !args.silent {
             println!(
-                "{} Wildcard detected! Filtering results pointing to: {}",
-                "[!]".yellow(),
-                ip.to_string().red()
+                "[*] Loaded {} words. Starting enumeration...",
+                subdomains.len()
             );
         }
-    }
 
-    let subdomains = load_wordlist(&args.wordlist).context("Failed to read wordlist")?;
-    if !args.silent {
-        println!(
-            "[*] Loaded {} words. Starting enumeration...",
-            subdomains.len()
-        );
+        let full_names: Vec<String> = subdomains
+            .into_iter()
+            .map(|sub| format!("{}.{}", sub, domain))
+            .collect();
+
+        scan_names(
+            full_names,
+            wildcard_ips,
+            &ctx,
+            found_domains.clone(),
+            takeovers.clone(),
+        )
+        .await;
+
+        None
+    };
+
+    if let Some(path) = args.output {
+        let results = found_domains.lock().await;
+        let takeovers = takeovers.lock().await;
+        let mut file = File::create(&path).context("Could not create output file")?;
+
+        if let Some(discovered) = &discovered_hosts {
+            for (ip, name) in discovered.lock().await.iter() {
+                writeln!(file, "{} => {}", ip, name)?;
+            }
+        }
+        for line in results.iter() {
+            writeln!(file, "{}", line)?;
+        }
+        if !takeovers.is_empty() {
+            writeln!(file, "\n# Potential takeovers")?;
+            for line in takeovers.iter() {
+                writeln!(file, "{}", line)?;
+            }
+        }
+        if !args.silent {
+            println!("\n[✓] Saved results to {:?}", path);
+        }
     }
 
-    let found_domains = Arc::new(Mutex::new(Vec::new()));
+    Ok(())
+}
 
-    // Pre-calculate timeout duration to avoid doing it inside the loop
-    let timeout_duration = Duration::from_secs(args.timeout);
+/// Shared state for the per-name concurrent lookup loops, used by both the
+/// forward (subdomain) and reverse (PTR) scans.
+struct ScanContext<'a> {
+    resolver: &'a TokioAsyncResolver,
+    dot_resolver: Option<&'a TokioAsyncResolver>,
+    use_dot: &'a Arc<AtomicBool>,
+    fingerprints: &'a [(String, String)],
+    concurrency: usize,
+    timeout_duration: Duration,
+    dispatch_interval: &'a Option<Arc<Mutex<tokio::time::Interval>>>,
+    silent: bool,
+}
+
+impl ScanContext<'_> {
+    /// Picks the DoT resolver when it's active, falling back to the plaintext one.
+    fn active_resolver(&self) -> &TokioAsyncResolver {
+        if self.use_dot.load(Ordering::Relaxed) {
+            self.dot_resolver.unwrap_or(self.resolver)
+        } else {
+            self.resolver
+        }
+    }
+}
 
-    let lookup_stream = stream::iter(subdomains);
+/// Resolves every name in `names` concurrently (the forward-resolution core), flagging
+/// wildcard noise and dangling-CNAME takeovers, and appends hits to `found_domains` /
+/// `takeovers`. Used for both the wordlist-driven scan and `--reverse --expand`.
+async fn scan_names(
+    names: Vec<String>,
+    wildcard_ips: Option<HashSet<String>>,
+    ctx: &ScanContext<'_>,
+    found_domains: Arc<Mutex<Vec<String>>>,
+    takeovers: Arc<Mutex<Vec<String>>>,
+) {
+    let lookup_stream = stream::iter(names);
 
     lookup_stream
-        .for_each_concurrent(args.concurrency, |sub| {
-            // Clone references for the async block
-            let resolver = &resolver;
-            let domain = &args.domain;
+        .for_each_concurrent(ctx.concurrency, |full_domain| {
             let found_domains = found_domains.clone();
-            let wildcard_ip = wildcard_ip.clone();
-            let silent = args.silent;
+            let takeovers = takeovers.clone();
+            let wildcard_ips = wildcard_ips.clone();
+            let silent = ctx.silent;
 
             async move {
-                let full_domain = format!("{}.{}", sub, domain);
+                if let Some(ticker) = ctx.dispatch_interval {
+                    ticker.lock().await.tick().await;
+                }
+
+                let resolver = ctx.active_resolver();
 
                 // FIX 2: Added actual Timeout logic using tokio::time::timeout
                 // This ensures the generic DNS lookup doesn't hang forever.
                 let lookup_future = resolver.lookup_ip(&full_domain);
 
-                match tokio::time::timeout(timeout_duration, lookup_future).await {
-                    // Timeout did not occur, and DNS resolution succeeded
-                    Ok(Ok(lookup)) => {
-                        if let Some(ip) = lookup.iter().next() {
-                            let ip_str = ip.to_string();
-
-                            // Filter out wildcard IPs
-                            let is_noise =
-                                wildcard_ip.as_ref().map_or(false, |w_ip| *w_ip == ip_str);
-
-                            if !is_noise {
-                                if !silent {
-                                    println!(
-                                        "{} {}  => {}",
-                                        "[+]".green(),
-                                        full_domain.bold(),
-                                        ip_str.dimmed()
-                                    );
-                                } else {
-                                    println!("{}", full_domain);
-                                }
-
-                                let mut lock = found_domains.lock().await;
-                                lock.push(full_domain);
+                let ip_result = tokio::time::timeout(ctx.timeout_duration, lookup_future).await;
+
+                if let Ok(Ok(lookup)) = &ip_result {
+                    let ips: Vec<String> = lookup.iter().map(|ip| ip.to_string()).collect();
+
+                    if let Some(ip_str) = ips.first() {
+                        // Suppress the result only if every IP it resolved to is also
+                        // something the wildcard probes returned.
+                        let is_noise = wildcard_ips
+                            .as_ref()
+                            .is_some_and(|set| ips.iter().all(|ip| set.contains(ip)));
+
+                        if !is_noise {
+                            if !silent {
+                                println!(
+                                    "{} {}  => {}",
+                                    "[+]".green(),
+                                    full_domain.bold(),
+                                    ip_str.dimmed()
+                                );
+                            } else {
+                                println!("{}", full_domain);
                             }
+
+                            let mut lock = found_domains.lock().await;
+                            lock.push(full_domain.clone());
+                        }
+                    }
+                }
+
+                // A/AAAA resolution failed or came up empty: check whether the name is
+                // a dangling CNAME pointing at a known third-party service.
+                let a_record_resolved = matches!(&ip_result, Ok(Ok(lookup)) if lookup.iter().next().is_some());
+                if !a_record_resolved {
+                    if let Some(ticker) = ctx.dispatch_interval {
+                        ticker.lock().await.tick().await;
+                    }
+
+                    if let Some(service) = check_takeover(
+                        resolver,
+                        &full_domain,
+                        ctx.fingerprints,
+                        ctx.timeout_duration,
+                    )
+                    .await
+                    {
+                        if !silent {
+                            println!(
+                                "{} {}  CNAME -> {}",
+                                "[TAKEOVER]".red().bold(),
+                                full_domain.bold(),
+                                service.red()
+                            );
+                        } else {
+                            println!("[TAKEOVER] {} -> {}", full_domain, service);
                         }
+
+                        let mut lock = takeovers.lock().await;
+                        lock.push(format!("{} -> {}", full_domain, service));
                     }
-                    // Ignore Timeouts (Ok(Err)) or DNS Errors (Err)
-                    _ => {}
                 }
             }
         })
         .await;
+}
 
-    if let Some(path) = args.output {
-        let results = found_domains.lock().await;
-        let mut file = File::create(&path).context("Could not create output file")?;
-        for line in results.iter() {
-            writeln!(file, "{}", line)?;
+/// Runs PTR lookups for every IP in `ips`, printing and collecting each `ip => name` hit.
+async fn reverse_scan(
+    ips: Vec<IpAddr>,
+    ctx: &ScanContext<'_>,
+) -> Arc<Mutex<Vec<(String, String)>>> {
+    let discovered = Arc::new(Mutex::new(Vec::new()));
+    let ip_stream = stream::iter(ips);
+
+    ip_stream
+        .for_each_concurrent(ctx.concurrency, |ip| {
+            let discovered = discovered.clone();
+            let silent = ctx.silent;
+
+            async move {
+                if let Some(ticker) = ctx.dispatch_interval {
+                    ticker.lock().await.tick().await;
+                }
+
+                let resolver = ctx.active_resolver();
+                let lookup_future = resolver.reverse_lookup(ip);
+
+                if let Ok(Ok(lookup)) =
+                    tokio::time::timeout(ctx.timeout_duration, lookup_future).await
+                {
+                    if let Some(name) = lookup.iter().next() {
+                        let name = name.to_string().trim_end_matches('.').to_string();
+
+                        if !silent {
+                            println!(
+                                "{} {}  => {}",
+                                "[+]".green(),
+                                ip.to_string().bold(),
+                                name.dimmed()
+                            );
+                        } else {
+                            println!("{} => {}", ip, name);
+                        }
+
+                        let mut lock = discovered.lock().await;
+                        lock.push((ip.to_string(), name));
+                    }
+                }
+            }
+        })
+        .await;
+
+    discovered
+}
+
+/// Gathers reverse-mode targets from `--cidr` and/or `--ip-file`.
+fn load_reverse_targets(args: &Args) -> Result<Vec<IpAddr>> {
+    let mut ips = Vec::new();
+
+    if let Some(cidr) = &args.cidr {
+        ips.extend(expand_cidr(cidr)?);
+    }
+    if let Some(path) = &args.ip_file {
+        ips.extend(load_ip_file(path)?);
+    }
+
+    if ips.is_empty() {
+        bail!("--reverse requires --cidr and/or --ip-file");
+    }
+
+    Ok(ips)
+}
+
+/// Expands an IPv4 CIDR block (e.g. "203.0.113.0/24") into its host addresses.
+fn expand_cidr(cidr: &str) -> Result<Vec<IpAddr>> {
+    let (addr_str, prefix_str) = cidr
+        .split_once('/')
+        .with_context(|| format!("Invalid CIDR {:?}, expected IP/prefix", cidr))?;
+    let addr: Ipv4Addr = addr_str
+        .parse()
+        .with_context(|| format!("Invalid CIDR address in {:?}", cidr))?;
+    let prefix: u32 = prefix_str
+        .parse()
+        .with_context(|| format!("Invalid CIDR prefix in {:?}", cidr))?;
+
+    if prefix > 32 {
+        bail!("CIDR prefix must be between 0 and 32, got {}", prefix);
+    }
+
+    let host_bits = 32 - prefix;
+    let count = 1u64 << host_bits;
+    if count > 1_000_000 {
+        bail!(
+            "CIDR block /{} expands to {} addresses, which is too large",
+            prefix,
+            count
+        );
+    }
+
+    let network = u32::from(addr) & (!0u64 << host_bits) as u32;
+
+    Ok((0..count as u32)
+        .map(|i| IpAddr::V4(Ipv4Addr::from(network + i)))
+        .collect())
+}
+
+fn load_ip_file(path: &PathBuf) -> Result<Vec<IpAddr>> {
+    let file = File::open(path).context("Could not open IP file")?;
+    let reader = BufReader::new(file);
+
+    reader
+        .lines()
+        .map_while(Result::ok)
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| {
+            l.parse::<IpAddr>()
+                .with_context(|| format!("Invalid IP address {:?}", l))
+        })
+        .collect()
+}
+
+/// Issues a CNAME lookup for `full_domain` and, if it points at a fingerprinted
+/// third-party service, returns the matched service name.
+async fn check_takeover(
+    resolver: &TokioAsyncResolver,
+    full_domain: &str,
+    fingerprints: &[(String, String)],
+    timeout_duration: Duration,
+) -> Option<String> {
+    let lookup_future = resolver.lookup(full_domain, RecordType::CNAME);
+    let lookup = tokio::time::timeout(timeout_duration, lookup_future)
+        .await
+        .ok()?
+        .ok()?;
+
+    lookup.iter().find_map(|record| {
+        let RData::CNAME(target) = record else {
+            return None;
+        };
+        let target = target.to_string().trim_end_matches('.').to_lowercase();
+        fingerprints
+            .iter()
+            .find(|(suffix, _)| target.ends_with(suffix.as_str()))
+            .map(|(_, service)| service.clone())
+    })
+}
+
+/// Loads the CNAME fingerprint table: any `--fingerprints FILE` entries
+/// ("suffix,service" per line) take priority over the built-in defaults, since
+/// `check_takeover` matches on the first suffix in the list that fits.
+fn load_fingerprints(path: Option<&std::path::Path>) -> Result<Vec<(String, String)>> {
+    let mut fingerprints: Vec<(String, String)> = Vec::new();
+
+    if let Some(path) = path {
+        let file = File::open(path).context("Could not open fingerprints file")?;
+        let reader = BufReader::new(file);
+
+        for line in reader.lines() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (suffix, service) = line
+                .split_once(',')
+                .with_context(|| format!("Invalid fingerprint line {:?}, expected suffix,service", line))?;
+            fingerprints.push((suffix.trim().to_lowercase(), service.trim().to_string()));
         }
-        if !args.silent {
-            println!("\n[✓] Saved {} results to {:?}", results.len(), path);
+    }
+
+    fingerprints.extend(
+        DEFAULT_FINGERPRINTS
+            .iter()
+            .map(|(suffix, service)| (suffix.to_string(), service.to_string())),
+    );
+
+    Ok(fingerprints)
+}
+
+/// Builds the resolver according to `--resolver` / `--resolver-file`, falling back to
+/// the library defaults when neither is given.
+fn build_resolver(args: &Args) -> Result<TokioAsyncResolver> {
+    if let Some(path) = &args.resolver_file {
+        let addrs = load_nameserver_file(path)?;
+        let config = resolver_config_from_addrs(&addrs);
+        let opts = multi_server_opts(addrs.len());
+        return Ok(TokioAsyncResolver::tokio(config, opts));
+    }
+
+    let values = match &args.resolver {
+        Some(values) => values,
+        None => {
+            return Ok(TokioAsyncResolver::tokio(
+                ResolverConfig::default(),
+                ResolverOpts::default(),
+            ))
+        }
+    };
+
+    if values.len() == 1 {
+        match values[0].as_str() {
+            "system" => {
+                return TokioAsyncResolver::tokio_from_system_conf()
+                    .context("Failed to read system resolver config")
+            }
+            "google" => {
+                return Ok(TokioAsyncResolver::tokio(
+                    ResolverConfig::google(),
+                    ResolverOpts::default(),
+                ))
+            }
+            "quad9" => {
+                return Ok(TokioAsyncResolver::tokio(
+                    ResolverConfig::quad9(),
+                    ResolverOpts::default(),
+                ))
+            }
+            "cloudflare" => {
+                return Ok(TokioAsyncResolver::tokio(
+                    ResolverConfig::cloudflare(),
+                    ResolverOpts::default(),
+                ))
+            }
+            _ => {}
         }
     }
 
-    Ok(())
+    let addrs: Vec<SocketAddr> = values
+        .iter()
+        .map(|v| parse_nameserver(v))
+        .collect::<Result<_>>()?;
+    let config = resolver_config_from_addrs(&addrs);
+    let opts = multi_server_opts(addrs.len());
+    Ok(TokioAsyncResolver::tokio(config, opts))
+}
+
+/// Resolver options for a pool of explicit nameservers. Shuffles the server order
+/// and disables the library's default statistics-driven bias toward whichever
+/// server currently looks fastest, so queries spread across the pool instead of
+/// concentrating on one (not literal round-robin: each query still only races
+/// `ResolverOpts::num_concurrent_reqs` servers at a time).
+fn multi_server_opts(server_count: usize) -> ResolverOpts {
+    let mut opts = ResolverOpts::default();
+    if server_count > 1 {
+        opts.shuffle_dns_servers = true;
+        opts.server_ordering_strategy = ServerOrderingStrategy::UserProvidedOrder;
+    }
+    opts
+}
+
+/// Builds a resolver that speaks DNS-over-TLS to `--dot-host:--dot-port`.
+fn build_dot_resolver(args: &Args) -> Result<TokioAsyncResolver> {
+    let socket_addr = format!("{}:{}", args.dot_host, args.dot_port)
+        .parse::<SocketAddr>()
+        .with_context(|| format!("Invalid DoT address {}:{}", args.dot_host, args.dot_port))?;
+
+    let mut config = ResolverConfig::new();
+    let mut name_server = NameServerConfig::new(socket_addr, Protocol::Tls);
+    name_server.tls_dns_name = Some(args.dot_host.clone());
+    config.add_name_server(name_server);
+
+    Ok(TokioAsyncResolver::tokio(config, ResolverOpts::default()))
+}
+
+fn load_nameserver_file(path: &PathBuf) -> Result<Vec<SocketAddr>> {
+    let file = File::open(path).context("Could not open resolver file")?;
+    let reader = BufReader::new(file);
+
+    reader
+        .lines()
+        .map_while(Result::ok)
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| parse_nameserver(&l))
+        .collect()
+}
+
+fn parse_nameserver(value: &str) -> Result<SocketAddr> {
+    value
+        .parse::<SocketAddr>()
+        .with_context(|| format!("Invalid nameserver address {:?}, expected IP:port", value))
+}
+
+/// Builds a `ResolverConfig` with both UDP and TCP entries for each nameserver.
+fn resolver_config_from_addrs(addrs: &[SocketAddr]) -> ResolverConfig {
+    let mut config = ResolverConfig::new();
+    for addr in addrs {
+        config.add_name_server(NameServerConfig::new(*addr, Protocol::Udp));
+        config.add_name_server(NameServerConfig::new(*addr, Protocol::Tcp));
+    }
+    config
 }
 
 fn load_wordlist(path: &PathBuf) -> Result<Vec<String>> {
@@ -149,7 +704,7 @@ fn load_wordlist(path: &PathBuf) -> Result<Vec<String>> {
 
     let words = reader
         .lines()
-        .filter_map(|line| line.ok())
+        .map_while(Result::ok)
         .map(|l| l.trim().to_string())
         .filter(|l| !l.is_empty() && !l.starts_with('#'))
         .collect();
@@ -157,20 +712,35 @@ fn load_wordlist(path: &PathBuf) -> Result<Vec<String>> {
     Ok(words)
 }
 
-async fn detect_wildcard(resolver: &TokioAsyncResolver, domain: &str) -> Option<String> {
-    let nonce = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .subsec_nanos();
-    let noise_domain = format!("wildcard-check-{}.{}", nonce, domain);
+/// Sends `probes` distinct random-nonce lookups and returns the union of every IP
+/// any of them resolved to, or `None` if none resolved at all (no wildcard).
+async fn detect_wildcard(
+    resolver: &TokioAsyncResolver,
+    domain: &str,
+    probes: usize,
+) -> Option<HashSet<String>> {
+    let mut ips = HashSet::new();
+
+    for i in 0..probes {
+        let nonce = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .subsec_nanos();
+        let noise_domain = format!("wildcard-check-{}-{}.{}", nonce, i, domain);
 
-    // We also apply a short timeout to the wildcard check
-    let lookup =
-        tokio::time::timeout(Duration::from_secs(3), resolver.lookup_ip(noise_domain)).await;
+        // We also apply a short timeout to each wildcard probe
+        let lookup =
+            tokio::time::timeout(Duration::from_secs(3), resolver.lookup_ip(noise_domain)).await;
+
+        if let Ok(Ok(lookup)) = lookup {
+            ips.extend(lookup.iter().map(|ip| ip.to_string()));
+        }
+    }
 
-    match lookup {
-        Ok(Ok(ips)) => ips.iter().next().map(|ip| ip.to_string()),
-        _ => None,
+    if ips.is_empty() {
+        None
+    } else {
+        Some(ips)
     }
 }
 
@@ -189,3 +759,41 @@ fn print_banner() {
         .blue()
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expand_cidr_slash_24_covers_the_block() {
+        let ips = expand_cidr("203.0.113.0/24").unwrap();
+        assert_eq!(ips.len(), 256);
+        assert_eq!(ips[0], IpAddr::V4(Ipv4Addr::new(203, 0, 113, 0)));
+        assert_eq!(ips[255], IpAddr::V4(Ipv4Addr::new(203, 0, 113, 255)));
+    }
+
+    #[test]
+    fn expand_cidr_slash_32_is_a_single_host() {
+        let ips = expand_cidr("203.0.113.5/32").unwrap();
+        assert_eq!(ips, vec![IpAddr::V4(Ipv4Addr::new(203, 0, 113, 5))]);
+    }
+
+    #[test]
+    fn expand_cidr_masks_host_bits_from_the_address() {
+        // The address isn't network-aligned; expansion should still start at
+        // the network address for the given prefix, not the address given.
+        let ips = expand_cidr("10.0.0.5/30").unwrap();
+        assert_eq!(ips.len(), 4);
+        assert_eq!(ips[0], IpAddr::V4(Ipv4Addr::new(10, 0, 0, 4)));
+    }
+
+    #[test]
+    fn expand_cidr_rejects_prefix_over_32() {
+        assert!(expand_cidr("10.0.0.0/33").is_err());
+    }
+
+    #[test]
+    fn expand_cidr_rejects_oversized_blocks() {
+        assert!(expand_cidr("10.0.0.0/0").is_err());
+    }
+}